@@ -0,0 +1,541 @@
+//! Layout node types produced by the standard library's layout functions.
+//!
+//! `Cast` impls for these types live here, next to their definitions, rather
+//! than next to each call site that happens to parse one.
+//!
+//! `resolve_tracks`, `resolve_stack_extent`, `wrap_flow`, and
+//! `resolve_margins` are pure sizing algorithms, not wired to a `Layout`
+//! impl: that trait and the measurement pass that would feed them real
+//! content sizes live in the layouter, which isn't part of this snapshot.
+//! Each node's doc comment in `crate::library::layout` notes which of these
+//! the layouter is expected to call and with what.
+
+use crate::eval::{BlockNode, Dict, Func, Value};
+use crate::geom::{
+    Align, Color, Dir, Fractional, Length, Linear, Paint, Sides, Size, Spec, SpecAxis,
+};
+
+/// How a grid or table track is sized.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum TrackSizing {
+    /// Fit the track to its content.
+    Auto,
+    /// A fixed or relative size.
+    Linear(Linear),
+    /// A fraction of the remaining space.
+    Fractional(Fractional),
+    /// Shrink the track to the minimum (unbreakable) size of its content.
+    Min,
+    /// Grow the track to the preferred (unconstrained) size of its content.
+    Max,
+}
+
+castable! {
+    TrackSizing: "auto, linear, fractional, \"min-content\", or \"max-content\"",
+    Value::Auto => Self::Auto,
+    Value::Length(v) => Self::Linear(v.into()),
+    Value::Relative(v) => Self::Linear(v.into()),
+    Value::Linear(v) => Self::Linear(v),
+    Value::Fractional(v) => Self::Fractional(v),
+    Value::Str(v) if v.as_str() == "min-content" => Self::Min,
+    Value::Str(v) if v.as_str() == "max-content" => Self::Max,
+}
+
+castable! {
+    Vec<TrackSizing>: "integer or (auto, linear, fractional, \"min-content\", \"max-content\", or array thereof)",
+    Value::Auto => vec![TrackSizing::Auto],
+    Value::Length(v) => vec![TrackSizing::Linear(v.into())],
+    Value::Relative(v) => vec![TrackSizing::Linear(v.into())],
+    Value::Linear(v) => vec![TrackSizing::Linear(v)],
+    Value::Fractional(v) => vec![TrackSizing::Fractional(v)],
+    Value::Int(count) => vec![TrackSizing::Auto; count.max(0) as usize],
+    Value::Str(v) if v.as_str() == "min-content" => vec![TrackSizing::Min],
+    Value::Str(v) if v.as_str() == "max-content" => vec![TrackSizing::Max],
+    Value::Array(values) => values
+        .into_iter()
+        .filter_map(|v| v.cast().ok())
+        .collect(),
+}
+
+/// Resolve a row or column of [`TrackSizing`]s to concrete lengths.
+///
+/// `sizes[i]` gives track `i`'s `(minimum, preferred)` content size (read
+/// only for `Auto`/`Min`/`Max` tracks; a missing entry counts as zero),
+/// measured by laying out its cells twice: once unbreakable, yielding a
+/// *minimum* (the longest unbreakable run), and once with no wrapping
+/// constraint, yielding a *preferred* size. `Linear` tracks resolve against
+/// `available`; `Fractional` tracks split whatever space is left over by
+/// their `fr` weight. `Auto` and `Max` both start from the preferred size,
+/// but `Auto` additionally shrinks — down to its minimum, never further —
+/// when the tracks don't otherwise fit in `available`.
+pub fn resolve_tracks(
+    sizing: &[TrackSizing],
+    available: Length,
+    sizes: &[(Length, Length)],
+) -> Vec<Length> {
+    let mut lengths = vec![Length::zero(); sizing.len()];
+    let mut used = Length::zero();
+    let mut fr_sum = 0.0_f64;
+
+    for (i, track) in sizing.iter().enumerate() {
+        let (min, preferred) = sizes.get(i).copied().unwrap_or_default();
+        match track {
+            TrackSizing::Linear(l) => lengths[i] = l.resolve(available),
+            TrackSizing::Min => lengths[i] = min,
+            TrackSizing::Auto | TrackSizing::Max => lengths[i] = preferred,
+            TrackSizing::Fractional(fr) => {
+                fr_sum += fr.get();
+                continue;
+            }
+        }
+        used += lengths[i];
+    }
+
+    // Shrink `Auto` tracks proportionally to their slack (preferred minus
+    // minimum) until the row/column fits, but never below their minimum.
+    if used > available {
+        let overflow = used - available;
+        let slack = sizing
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| matches!(t, TrackSizing::Auto))
+            .fold(Length::zero(), |acc, (i, _)| {
+                let (min, _) = sizes.get(i).copied().unwrap_or_default();
+                acc + (lengths[i] - min)
+            });
+
+        if slack.to_pt() > 0.0 {
+            let ratio = (overflow.to_pt() / slack.to_pt()).min(1.0);
+            for (i, track) in sizing.iter().enumerate() {
+                if matches!(track, TrackSizing::Auto) {
+                    let (min, _) = sizes.get(i).copied().unwrap_or_default();
+                    let shrinkable = lengths[i] - min;
+                    lengths[i] = lengths[i] - shrinkable * ratio;
+                }
+            }
+            used = used - slack * ratio;
+        }
+    }
+
+    if fr_sum > 0.0 {
+        let remaining = if available > used { available - used } else { Length::zero() };
+        for (i, track) in sizing.iter().enumerate() {
+            if let TrackSizing::Fractional(fr) = track {
+                lengths[i] = remaining * (fr.get() / fr_sum);
+            }
+        }
+    }
+
+    lengths
+}
+
+/// Shrink-to-fit the main-axis extent of a [`StackNode`]'s children: each
+/// child acts as its own `Auto` track, so the stack takes the sum of their
+/// preferred sizes, shrinking proportionally toward their minimums if that
+/// sum doesn't fit in `available` — the same policy [`resolve_tracks`] uses
+/// for a single `Auto` track, just summed across the whole stack.
+pub fn resolve_stack_extent(sizes: &[(Length, Length)], available: Length) -> Length {
+    let sizing = vec![TrackSizing::Auto; sizes.len()];
+    resolve_tracks(&sizing, available, sizes)
+        .into_iter()
+        .fold(Length::zero(), |acc, len| acc + len)
+}
+
+/// A line stroke, as used by `box`, `block`, and table cells.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Stroke {
+    pub paint: Paint,
+    pub thickness: Length,
+}
+
+impl Stroke {
+    /// A stroke of the default paint with the given thickness.
+    pub fn with_thickness(thickness: Length) -> Self {
+        Self { paint: Paint::Color(Color::BLACK), thickness }
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self::with_thickness(Length::pt(1.0))
+    }
+}
+
+castable! {
+    Stroke: "length, color, or dictionary with paint and thickness",
+    Value::Length(v) => Self::with_thickness(v.into()),
+    Value::Relative(v) => Self::with_thickness(v.into()),
+    Value::Color(v) => Self { paint: Paint::Color(v), ..Self::default() },
+    Value::Dict(mut v) => Self::from_dict(&mut v),
+}
+
+impl Stroke {
+    /// Take the `paint` and `thickness` entries out of a dictionary, using
+    /// the default stroke's value for each one that is missing.
+    fn from_dict(dict: &mut Dict) -> Self {
+        let paint = dict.take("paint").ok().and_then(|v| v.cast::<Color>().ok());
+        let thickness = dict.take("thickness").ok().and_then(|v| v.cast::<Length>().ok());
+        Self {
+            paint: paint.map(Paint::Color).unwrap_or_else(|| Self::default().paint),
+            thickness: thickness.unwrap_or_else(|| Self::default().thickness),
+        }
+    }
+
+    /// Parse a dictionary of the form `(paint: color, thickness: length,
+    /// sides: (left: .., top: .., right: .., bottom: ..))`. `paint` and
+    /// `thickness` set the shared stroke that applies to every side; `sides`
+    /// then lets individual sides override it.
+    fn sides_from_dict(mut dict: Dict) -> Sides<Option<Self>> {
+        let base = Self::from_dict(&mut dict);
+
+        let mut sides = Sides::splat(Some(base));
+        if let Some(mut per_side) = dict.take("sides").ok().and_then(|v| v.cast::<Dict>().ok()) {
+            if let Some(v) = per_side.take("left").ok().and_then(|v| v.cast().ok()) {
+                sides.left = Some(v);
+            }
+            if let Some(v) = per_side.take("top").ok().and_then(|v| v.cast().ok()) {
+                sides.top = Some(v);
+            }
+            if let Some(v) = per_side.take("right").ok().and_then(|v| v.cast().ok()) {
+                sides.right = Some(v);
+            }
+            if let Some(v) = per_side.take("bottom").ok().and_then(|v| v.cast().ok()) {
+                sides.bottom = Some(v);
+            }
+        }
+
+        sides
+    }
+}
+
+castable! {
+    Sides<Option<Stroke>>: "length, color, or dictionary with paint, thickness, and sides",
+    Value::Length(v) => Sides::splat(Some(Stroke::with_thickness(v.into()))),
+    Value::Relative(v) => Sides::splat(Some(Stroke::with_thickness(v.into()))),
+    Value::Color(v) => Sides::splat(Some(Stroke { paint: Paint::Color(v), ..Stroke::default() })),
+    Value::Dict(v) => Stroke::sides_from_dict(v),
+}
+
+/// The outward growth a shape needs to fully contain a stroke drawn along
+/// its outline: strokes are centered on the outline, so half the thickness
+/// falls outside it.
+pub fn stroke_outset(stroke: Option<&Stroke>) -> Length {
+    stroke.map(|s| s.thickness * 0.5).unwrap_or_else(Length::zero)
+}
+
+/// Per-side outward growth needed to fully contain a per-side stroke; see
+/// [`stroke_outset`] for the rationale.
+pub fn stroke_outset_sides(sides: &Sides<Option<Stroke>>) -> Sides<Length> {
+    Sides::new(
+        stroke_outset(sides.left.as_ref()),
+        stroke_outset(sides.top.as_ref()),
+        stroke_outset(sides.right.as_ref()),
+        stroke_outset(sides.bottom.as_ref()),
+    )
+}
+
+/// The fill of a table, possibly varying per cell.
+pub enum TableFill {
+    /// The same color for every cell.
+    Color(Color),
+    /// A sequence of colors, cycled by column.
+    Cycle(Vec<Color>),
+    /// A function from `(row, col)` to a color, resolved during layout.
+    Func(Func),
+}
+
+castable! {
+    TableFill: "color, array of colors, or function",
+    Value::Color(v) => Self::Color(v),
+    Value::Array(v) => Self::Cycle(v.into_iter().filter_map(|v| v.cast().ok()).collect()),
+    Value::Func(v) => Self::Func(v),
+}
+
+impl TableFill {
+    /// Resolve the fill for a cell in the given column, for the variants
+    /// that don't require invoking user code. `Func` fills are resolved by
+    /// the layouter once it knows the table's full row/column count.
+    pub fn resolve_static(&self, col: usize) -> Option<Color> {
+        match self {
+            Self::Color(c) => Some(*c),
+            Self::Cycle(colors) if !colors.is_empty() => Some(colors[col % colors.len()]),
+            Self::Cycle(_) | Self::Func(_) => None,
+        }
+    }
+}
+
+/// A single cell in a [`TableNode`].
+pub struct TableCell {
+    pub body: BlockNode,
+    pub span: Spec<usize>,
+    pub fill: Option<Color>,
+    pub align: Option<Align>,
+    pub inset: Option<Sides<Linear>>,
+}
+
+/// A table with headers, per-cell styling, and cell strokes.
+pub struct TableNode {
+    pub tracks: Spec<Vec<TrackSizing>>,
+    pub gutter: Spec<Vec<TrackSizing>>,
+    pub header_rows: usize,
+    pub stroke: Sides<Option<Stroke>>,
+    pub fill: Option<TableFill>,
+    pub cells: Vec<TableCell>,
+}
+
+/// Children laid out along an axis, wrapping onto a new line/column once
+/// they exceed the available extent.
+pub struct FlowNode {
+    pub dir: Dir,
+    pub spacing: Linear,
+    pub children: Vec<BlockNode>,
+}
+
+/// Group child extents into wrapped lines along the main axis.
+///
+/// Each line greedily collects consecutive children, in order, as long as
+/// they (plus `spacing` between them) fit within `available`. A child wider
+/// than `available` on its own still gets a line of its own rather than
+/// being dropped or causing an infinite loop.
+pub fn wrap_flow(extents: &[Length], available: Length, spacing: Length) -> Vec<Vec<usize>> {
+    let mut lines = Vec::new();
+    let mut line = Vec::new();
+    let mut used = Length::zero();
+
+    for (i, &extent) in extents.iter().enumerate() {
+        let needed = if line.is_empty() { extent } else { used + spacing + extent };
+        if !line.is_empty() && needed > available {
+            lines.push(std::mem::take(&mut line));
+            used = Length::zero();
+        }
+
+        let needed = if line.is_empty() { extent } else { used + spacing + extent };
+        line.push(i);
+        used = needed;
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// A value that is either automatically determined or explicitly set.
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub enum Smart<T> {
+    /// Let the layouter determine the value.
+    Auto,
+    /// An explicit value.
+    Custom(T),
+}
+
+castable! {
+    Smart<Linear>: "auto or linear",
+    Value::Auto => Self::Auto,
+    Value::Length(v) => Self::Custom(v.into()),
+    Value::Relative(v) => Self::Custom(v.into()),
+    Value::Linear(v) => Self::Custom(v),
+}
+
+/// Resolve page margins against the page and content size, centering the
+/// content when a side is `auto`: when both sides of an axis are `auto`,
+/// the leftover space (page size minus content size) is split evenly
+/// between them; when one side is `auto` and the other fixed, the leftover
+/// space after the fixed side is given entirely to the `auto` side, pushing
+/// the content against the fixed side.
+pub fn resolve_margins(
+    margins: Sides<Smart<Linear>>,
+    page: Size,
+    content: Size,
+) -> Sides<Length> {
+    let axis = |low: Smart<Linear>, high: Smart<Linear>, page_len: Length, content_len: Length| {
+        let leftover = if page_len > content_len {
+            page_len - content_len
+        } else {
+            Length::zero()
+        };
+
+        match (low, high) {
+            (Smart::Custom(low), Smart::Custom(high)) => {
+                (low.resolve(page_len), high.resolve(page_len))
+            }
+            (Smart::Auto, Smart::Auto) => {
+                let half = leftover * 0.5;
+                (half, half)
+            }
+            (Smart::Custom(low), Smart::Auto) => {
+                let low = low.resolve(page_len);
+                let high = if leftover > low { leftover - low } else { Length::zero() };
+                (low, high)
+            }
+            (Smart::Auto, Smart::Custom(high)) => {
+                let high = high.resolve(page_len);
+                let low = if leftover > high { leftover - high } else { Length::zero() };
+                (low, high)
+            }
+        }
+    };
+
+    let (left, right) = axis(margins.left, margins.right, page.w, content.w);
+    let (top, bottom) = axis(margins.top, margins.bottom, page.h, content.h);
+    Sides::new(left, top, right, bottom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_fill_cycles_by_column() {
+        let fill = TableFill::Cycle(vec![Color::BLACK, Color::WHITE]);
+        assert_eq!(fill.resolve_static(0), Some(Color::BLACK));
+        assert_eq!(fill.resolve_static(1), Some(Color::WHITE));
+        assert_eq!(fill.resolve_static(2), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn table_fill_color_ignores_column() {
+        let fill = TableFill::Color(Color::BLACK);
+        assert_eq!(fill.resolve_static(0), Some(Color::BLACK));
+        assert_eq!(fill.resolve_static(41), Some(Color::BLACK));
+    }
+
+    #[test]
+    fn table_fill_empty_cycle_resolves_to_none() {
+        // An empty cycle has no color to hand out for any column.
+        let empty = TableFill::Cycle(vec![]);
+        assert_eq!(empty.resolve_static(0), None);
+    }
+
+    #[test]
+    fn stroke_outset_is_half_the_thickness() {
+        let stroke = Stroke::with_thickness(Length::pt(2.0));
+        assert_eq!(stroke_outset(Some(&stroke)), Length::pt(1.0));
+    }
+
+    #[test]
+    fn stroke_outset_without_stroke_is_zero() {
+        assert_eq!(stroke_outset(None), Length::zero());
+    }
+
+    #[test]
+    fn stroke_outset_sides_mixes_set_and_unset_sides() {
+        let sides = Sides::new(
+            Some(Stroke::with_thickness(Length::pt(2.0))),
+            None,
+            Some(Stroke::with_thickness(Length::pt(4.0))),
+            None,
+        );
+        let outset = stroke_outset_sides(&sides);
+        assert_eq!(outset.left, Length::pt(1.0));
+        assert_eq!(outset.top, Length::zero());
+        assert_eq!(outset.right, Length::pt(2.0));
+        assert_eq!(outset.bottom, Length::zero());
+    }
+
+    #[test]
+    fn auto_track_takes_preferred_size_when_it_fits() {
+        let sizing = vec![TrackSizing::Auto];
+        let sizes = vec![(Length::pt(5.0), Length::pt(20.0))];
+        let lengths = resolve_tracks(&sizing, Length::pt(100.0), &sizes);
+        assert_eq!(lengths, vec![Length::pt(20.0)]);
+    }
+
+    #[test]
+    fn auto_track_shrinks_but_not_below_minimum() {
+        let sizing = vec![TrackSizing::Auto, TrackSizing::Auto];
+        let sizes = vec![(Length::pt(10.0), Length::pt(60.0)), (Length::pt(10.0), Length::pt(60.0))];
+        let lengths = resolve_tracks(&sizing, Length::pt(80.0), &sizes);
+        // Both tracks shrink equally from 60pt toward their 10pt minimum
+        // until the total of 80pt fits.
+        assert_eq!(lengths, vec![Length::pt(40.0), Length::pt(40.0)]);
+    }
+
+    #[test]
+    fn min_and_max_tracks_ignore_available_space() {
+        let sizing = vec![TrackSizing::Min, TrackSizing::Max];
+        let sizes = vec![(Length::pt(10.0), Length::pt(60.0)), (Length::pt(15.0), Length::pt(45.0))];
+        let lengths = resolve_tracks(&sizing, Length::pt(10.0), &sizes);
+        assert_eq!(lengths, vec![Length::pt(10.0), Length::pt(45.0)]);
+    }
+
+    #[test]
+    fn stack_extent_sums_preferred_sizes_when_they_fit() {
+        let sizes = vec![(Length::pt(5.0), Length::pt(20.0)), (Length::pt(5.0), Length::pt(30.0))];
+        assert_eq!(resolve_stack_extent(&sizes, Length::pt(100.0)), Length::pt(50.0));
+    }
+
+    #[test]
+    fn stack_extent_shrinks_to_fit_available_space() {
+        let sizes = vec![(Length::pt(10.0), Length::pt(60.0)), (Length::pt(10.0), Length::pt(60.0))];
+        assert_eq!(resolve_stack_extent(&sizes, Length::pt(80.0)), Length::pt(80.0));
+    }
+
+    #[test]
+    fn wrap_flow_keeps_children_on_one_line_when_they_fit() {
+        let extents = vec![Length::pt(10.0), Length::pt(10.0), Length::pt(10.0)];
+        let lines = wrap_flow(&extents, Length::pt(100.0), Length::pt(2.0));
+        assert_eq!(lines, vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn wrap_flow_starts_a_new_line_on_overflow() {
+        let extents = vec![Length::pt(40.0), Length::pt(40.0), Length::pt(40.0)];
+        let lines = wrap_flow(&extents, Length::pt(100.0), Length::pt(10.0));
+        assert_eq!(lines, vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn wrap_flow_gives_an_oversized_child_its_own_line() {
+        let extents = vec![Length::pt(10.0), Length::pt(200.0), Length::pt(10.0)];
+        let lines = wrap_flow(&extents, Length::pt(100.0), Length::pt(5.0));
+        assert_eq!(lines, vec![vec![0], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn fractional_tracks_split_leftover_space() {
+        let sizing =
+            vec![TrackSizing::Linear(Linear::from(Length::pt(20.0))), TrackSizing::Fractional(Fractional::one())];
+        let sizes = vec![(Length::zero(), Length::zero()), (Length::zero(), Length::zero())];
+        let lengths = resolve_tracks(&sizing, Length::pt(100.0), &sizes);
+        assert_eq!(lengths, vec![Length::pt(20.0), Length::pt(80.0)]);
+    }
+
+    #[test]
+    fn auto_margins_on_both_sides_center_the_content() {
+        let margins = Sides::splat(Smart::Auto);
+        let page = Size::new(Length::pt(100.0), Length::pt(200.0));
+        let content = Size::new(Length::pt(60.0), Length::pt(150.0));
+        let resolved = resolve_margins(margins, page, content);
+        assert_eq!(resolved.left, Length::pt(20.0));
+        assert_eq!(resolved.right, Length::pt(20.0));
+        assert_eq!(resolved.top, Length::pt(25.0));
+        assert_eq!(resolved.bottom, Length::pt(25.0));
+    }
+
+    #[test]
+    fn fixed_margin_pushes_content_against_the_fixed_side() {
+        let margins = Sides::new(Smart::Custom(Linear::from(Length::pt(10.0))), Smart::Auto, Smart::Auto, Smart::Auto);
+        let page = Size::new(Length::pt(100.0), Length::pt(200.0));
+        let content = Size::new(Length::pt(60.0), Length::pt(150.0));
+        let resolved = resolve_margins(margins, page, content);
+        assert_eq!(resolved.left, Length::pt(10.0));
+        assert_eq!(resolved.right, Length::pt(30.0));
+    }
+
+    #[test]
+    fn both_margins_fixed_are_used_verbatim() {
+        let margins = Sides::new(
+            Smart::Custom(Linear::from(Length::pt(5.0))),
+            Smart::Auto,
+            Smart::Custom(Linear::from(Length::pt(15.0))),
+            Smart::Auto,
+        );
+        let page = Size::new(Length::pt(100.0), Length::pt(200.0));
+        let content = Size::new(Length::pt(60.0), Length::pt(150.0));
+        let resolved = resolve_margins(margins, page, content);
+        assert_eq!(resolved.left, Length::pt(5.0));
+        assert_eq!(resolved.right, Length::pt(15.0));
+    }
+}
@@ -1,10 +1,16 @@
 use super::*;
 use crate::layout::{
-    GridNode, PadNode, ShapeKind, ShapeNode, StackChild, StackNode, TrackSizing,
+    FlowNode, GridNode, PadNode, ShapeKind, ShapeNode, Smart, StackChild, StackNode,
+    Stroke, TableCell, TableFill, TableNode, TrackSizing,
 };
 use crate::style::{Paper, PaperClass};
 
 /// `page`: Configure pages.
+///
+/// Margins may be `auto`, centering the content within the leftover space.
+/// `page()` only records the `Smart<Linear>` margins here; the layouter
+/// resolves `auto` sides via `crate::layout::resolve_margins` once it knows
+/// the page and content sizes.
 pub fn page(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
     let paper = match args.named::<Spanned<Str>>("paper")?.or_else(|| args.eat()) {
         Some(name) => match Paper::from_name(&name.v) {
@@ -16,11 +22,11 @@ pub fn page(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
 
     let width = args.named("width")?;
     let height = args.named("height")?;
-    let margins = args.named("margins")?;
-    let left = args.named("left")?;
-    let top = args.named("top")?;
-    let right = args.named("right")?;
-    let bottom = args.named("bottom")?;
+    let margins = args.named::<Smart<Linear>>("margins")?;
+    let left = args.named::<Smart<Linear>>("left")?;
+    let top = args.named::<Smart<Linear>>("top")?;
+    let right = args.named::<Smart<Linear>>("right")?;
+    let bottom = args.named::<Smart<Linear>>("bottom")?;
     let flip = args.named("flip")?;
 
     let page = ctx.style.page_mut();
@@ -41,23 +47,23 @@ pub fn page(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
     }
 
     if let Some(margins) = margins {
-        page.margins = Sides::splat(Some(margins));
+        page.margins = Sides::splat(margins);
     }
 
     if let Some(left) = left {
-        page.margins.left = Some(left);
+        page.margins.left = left;
     }
 
     if let Some(top) = top {
-        page.margins.top = Some(top);
+        page.margins.top = top;
     }
 
     if let Some(right) = right {
-        page.margins.right = Some(right);
+        page.margins.right = right;
     }
 
     if let Some(bottom) = bottom {
-        page.margins.bottom = Some(bottom);
+        page.margins.bottom = bottom;
     }
 
     if flip.unwrap_or(false) {
@@ -130,20 +136,56 @@ pub fn boxed(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
     let width = args.named("width")?;
     let height = args.named("height")?;
     let fill = args.named("fill")?;
+    let stroke = args
+        .named::<Sides<Option<Stroke>>>("stroke")?
+        .unwrap_or_else(|| Sides::splat(None));
+    let radius = args.named("radius")?;
     let body: Node = args.eat().unwrap_or_default();
     Ok(Value::inline(ShapeNode {
         shape: ShapeKind::Rect,
         width,
         height,
         fill: fill.map(Paint::Color),
+        stroke,
+        radius,
         child: Some(body.to_block(&ctx.style)),
     }))
 }
 
-/// `block`: Place content in a block.
+/// `block`: Place content in a block, optionally with a fill, stroke, and size.
 pub fn block(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
+    let width = args.named("width")?;
+    let height = args.named("height")?;
+    let fill = args.named("fill")?;
+    let stroke = args
+        .named::<Sides<Option<Stroke>>>("stroke")?
+        .unwrap_or_else(|| Sides::splat(None));
+    let radius = args.named("radius")?;
     let body: Node = args.expect("body")?;
-    Ok(Value::block(body.to_block(&ctx.style)))
+
+    let stroke_is_empty = stroke.left.is_none()
+        && stroke.top.is_none()
+        && stroke.right.is_none()
+        && stroke.bottom.is_none();
+
+    if width.is_none()
+        && height.is_none()
+        && fill.is_none()
+        && stroke_is_empty
+        && radius.is_none()
+    {
+        return Ok(Value::block(body.to_block(&ctx.style)));
+    }
+
+    Ok(Value::block(ShapeNode {
+        shape: ShapeKind::Rect,
+        width,
+        height,
+        fill: fill.map(Paint::Color),
+        stroke,
+        radius,
+        child: Some(body.to_block(&ctx.style)),
+    }))
 }
 
 /// `pad`: Pad content at the sides.
@@ -169,6 +211,11 @@ pub fn pad(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
 }
 
 /// `stack`: Stack children along an axis.
+///
+/// A horizontal stack shrinks to fit its children instead of claiming the
+/// full available width: the layouter measures each child's min/preferred
+/// size and sums them with [`crate::layout::resolve_stack_extent`], the same
+/// shrink-toward-minimum policy `grid`/`table` apply to an `auto` track.
 pub fn stack(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
     enum Child {
         Spacing(Linear),
@@ -208,34 +255,42 @@ pub fn stack(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
         }
     }
 
-    Ok(Value::block(StackNode { dir, children }))
+    Ok(Value::block(StackNode {
+        dir,
+        children,
+        shrink: dir.axis() == SpecAxis::Horizontal,
+    }))
+}
+
+/// `flow`: Lay out children along an axis, wrapping onto a new line when they
+/// exceed the available extent.
+///
+/// `flow()` only builds the unwrapped child list; the layouter measures each
+/// child's extent and calls `crate::layout::wrap_flow` to decide line breaks
+/// once those measurements exist.
+pub fn flow(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
+    let dir = args.named("dir")?;
+    let axis = args.named("axis")?;
+    let dir = dir.or(axis).unwrap_or(Dir::LTR);
+    let spacing = args.named::<Linear>("spacing")?.unwrap_or_default();
+
+    let children = args
+        .all()
+        .map(|node: Node| node.to_block(&ctx.style))
+        .collect();
+
+    Ok(Value::block(FlowNode { dir, spacing, children }))
 }
 
 /// `grid`: Arrange children into a grid.
+///
+/// `TrackSizing`'s `Cast` impls live in `crate::layout`, next to the type
+/// itself, so `grid` and `table` share a single impl instead of each
+/// registering their own. `grid()` only records each track's `TrackSizing`;
+/// the layouter measures cell content and calls
+/// `crate::layout::resolve_tracks` once it knows each track's min/preferred
+/// size.
 pub fn grid(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
-    castable! {
-        Vec<TrackSizing>: "integer or (auto, linear, fractional, or array thereof)",
-        Value::Auto => vec![TrackSizing::Auto],
-        Value::Length(v) => vec![TrackSizing::Linear(v.into())],
-        Value::Relative(v) => vec![TrackSizing::Linear(v.into())],
-        Value::Linear(v) => vec![TrackSizing::Linear(v)],
-        Value::Fractional(v) => vec![TrackSizing::Fractional(v)],
-        Value::Int(count) => vec![TrackSizing::Auto; count.max(0) as usize],
-        Value::Array(values) => values
-            .into_iter()
-            .filter_map(|v| v.cast().ok())
-            .collect(),
-    }
-
-    castable! {
-        TrackSizing: "auto, linear, or fractional",
-        Value::Auto => Self::Auto,
-        Value::Length(v) => Self::Linear(v.into()),
-        Value::Relative(v) => Self::Linear(v.into()),
-        Value::Linear(v) => Self::Linear(v),
-        Value::Fractional(v) => Self::Fractional(v),
-    }
-
     let columns = args.named("columns")?.unwrap_or_default();
     let rows = args.named("rows")?.unwrap_or_default();
     let tracks = Spec::new(columns, rows);
@@ -252,3 +307,65 @@ pub fn grid(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
 
     Ok(Value::block(GridNode { tracks, gutter, children }))
 }
+
+/// `table`: Arrange children into a table with headers and per-cell styling.
+pub fn table(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
+    let columns = args.named("columns")?.unwrap_or_default();
+    let rows = args.named("rows")?.unwrap_or_default();
+    let tracks = Spec::new(columns, rows);
+
+    let base_gutter: Vec<TrackSizing> = args.named("gutter")?.unwrap_or_default();
+    let column_gutter = args.named("column-gutter")?;
+    let row_gutter = args.named("row-gutter")?;
+    let gutter = Spec::new(
+        column_gutter.unwrap_or_else(|| base_gutter.clone()),
+        row_gutter.unwrap_or(base_gutter),
+    );
+
+    let header_rows = args.named::<i64>("header-rows")?.unwrap_or(0).max(0) as usize;
+    let stroke = args
+        .named::<Sides<Option<Stroke>>>("stroke")?
+        .unwrap_or_else(|| Sides::splat(None));
+    let fill: Option<TableFill> = args.named("fill")?;
+
+    let cells = args
+        .all()
+        .map(|node: Node| match node {
+            Node::TableCell(cell) => cell,
+            other => TableCell {
+                body: other.to_block(&ctx.style),
+                span: Spec::splat(1),
+                fill: None,
+                align: None,
+                inset: None,
+            },
+        })
+        .collect();
+
+    Ok(Value::block(TableNode {
+        tracks,
+        gutter,
+        header_rows,
+        stroke,
+        fill,
+        cells,
+    }))
+}
+
+/// `cell`: Style a single table cell's fill, alignment, inset, or span.
+pub fn cell(ctx: &mut EvalContext, args: &mut Args) -> TypResult<Value> {
+    let fill = args.named("fill")?;
+    let align = args.named("align")?;
+    let inset = args.named("inset")?;
+    let colspan = args.named::<i64>("colspan")?.unwrap_or(1).max(1) as usize;
+    let rowspan = args.named::<i64>("rowspan")?.unwrap_or(1).max(1) as usize;
+    let body: Node = args.expect("body")?;
+
+    Ok(Value::Node(Node::TableCell(TableCell {
+        body: body.to_block(&ctx.style),
+        span: Spec::new(colspan, rowspan),
+        fill,
+        align,
+        inset,
+    })))
+}
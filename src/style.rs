@@ -0,0 +1,115 @@
+//! Cascading style properties threaded through evaluation.
+//!
+//! `EvalContext::style` holds the current [`Style`]; library functions like
+//! `page`/`align` mutate it in place so that later siblings and children
+//! inherit the change, the same way CSS properties cascade.
+
+use crate::geom::{Align, Length, Linear, Sides, Size};
+use crate::layout::Smart;
+
+/// Which family of paper sizes a page's size was derived from, used by the
+/// layouter to decide things like default margins for the class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaperClass {
+    /// The size was set explicitly rather than taken from a named paper.
+    Custom,
+    /// ISO 216 "A" series (A4, A5, ...).
+    A,
+    /// North American sizes (US Letter, Legal, ...).
+    Us,
+}
+
+/// The active style properties, grouped by the area of layout they affect.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub page: Page,
+    pub text: TextStyle,
+    pub par: ParStyle,
+}
+
+impl Style {
+    pub fn page_mut(&mut self) -> &mut Page {
+        &mut self.page
+    }
+
+    pub fn text_mut(&mut self) -> &mut TextStyle {
+        &mut self.text
+    }
+
+    pub fn par_mut(&mut self) -> &mut ParStyle {
+        &mut self.par
+    }
+}
+
+/// Page-level style: size, class, and margins.
+///
+/// `margins` is `Sides<Smart<Linear>>`, not `Sides<Option<Linear>>`: a side
+/// that's `auto` still has a well-defined meaning (let the layouter center
+/// the content via `crate::layout::resolve_margins`), whereas leaving it out
+/// entirely would have no default to fall back on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub class: PaperClass,
+    pub size: Size,
+    pub margins: Sides<Smart<Linear>>,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        let margin = Smart::Custom(Linear::from(Length::cm(2.5)));
+        Self {
+            class: PaperClass::Custom,
+            size: Size::new(Length::cm(21.0), Length::cm(29.7)),
+            margins: Sides::splat(margin),
+        }
+    }
+}
+
+/// Text-level style, e.g. the horizontal reading alignment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    pub align: Align,
+}
+
+/// Paragraph-level style, e.g. the alignment used to lay out block children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParStyle {
+    pub align: Align,
+}
+
+/// A named paper size, looked up by `page(paper: "...")`.
+///
+/// Real paper databases list dozens of sizes; this only keeps the handful
+/// needed to exercise `page()`'s class/size resolution, the same way this
+/// snapshot leaves other large reference tables (e.g. the font book) out of
+/// scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Paper {
+    A4,
+    UsLetter,
+}
+
+impl Paper {
+    /// Look up a paper by its lowercase, hyphenated name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "a4" => Some(Self::A4),
+            "us-letter" => Some(Self::UsLetter),
+            _ => None,
+        }
+    }
+
+    pub fn class(self) -> PaperClass {
+        match self {
+            Self::A4 => PaperClass::A,
+            Self::UsLetter => PaperClass::Us,
+        }
+    }
+
+    pub fn size(self) -> Size {
+        match self {
+            Self::A4 => Size::new(Length::mm(210.0), Length::mm(297.0)),
+            Self::UsLetter => Size::new(Length::inches(8.5), Length::inches(11.0)),
+        }
+    }
+}